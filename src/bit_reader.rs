@@ -0,0 +1,28 @@
+/// Reads a byte slice one bit at a time, least-significant-bit first,
+/// matching the order bits are packed into bytes by `encode`.
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    current_bit: u8,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            bytes,
+            offset: 0,
+            current_bit: 0,
+        }
+    }
+
+    pub fn read_bit(&mut self) -> Option<bool> {
+        let byte = *self.bytes.get(self.offset)?;
+        let bit = (byte >> self.current_bit) & 1 == 1;
+        self.current_bit += 1;
+        if self.current_bit == 8 {
+            self.current_bit = 0;
+            self.offset += 1;
+        }
+        Some(bit)
+    }
+}
@@ -0,0 +1,74 @@
+//! Minimal `Read`/`Write` abstraction so the encode/decode core can run
+//! under `no_std` + `alloc`. With the `std` feature, any `std::io::Read`/
+//! `std::io::Write` gets a blanket impl for free; without it, callers
+//! implement these directly over whatever byte source/sink they have (an
+//! in-memory `&[u8]`/`Vec<u8>` impl is provided either way).
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(not(feature = "std"))]
+use core::error::Error;
+
+/// A byte source the encode/decode core can slurp entirely into memory.
+pub trait Read {
+    type Error: Error + 'static;
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error>;
+}
+
+/// A byte sink the encode/decode core writes compressed or decompressed
+/// output to.
+pub trait Write {
+    type Error: Error + 'static;
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+}
+
+// Under `std`, `&mut W` already gets the blanket impl below for free
+// (`&mut W: std::io::Write` whenever `W: std::io::Write`); this manual
+// forwarding impl only needs to exist for `no_std`, where `Vec<u8>`'s
+// `Write` impl is ours to begin with.
+#[cfg(not(feature = "std"))]
+impl<W: Write + ?Sized> Write for &mut W {
+    type Error = W::Error;
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(buf)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Read for R {
+    type Error = std::io::Error;
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error> {
+        std::io::Read::read_to_end(self, buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Write for W {
+    type Error = std::io::Error;
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        std::io::Write::write_all(self, buf)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Read for &[u8] {
+    type Error = core::convert::Infallible;
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error> {
+        buf.extend_from_slice(self);
+        *self = &self[self.len()..];
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for Vec<u8> {
+    type Error = core::convert::Infallible;
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
@@ -1,14 +1,35 @@
-use std::cmp::{Ordering, Reverse};
-use std::collections::{BinaryHeap, HashMap};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+use core::cmp::{Ordering, Reverse};
+use core::convert::TryInto;
+use core::fmt::Display;
+use core::hash::Hash;
+
+#[cfg(feature = "std")]
+use std::collections::{BinaryHeap, HashMap as Map};
+#[cfg(not(feature = "std"))]
+use alloc::collections::{BTreeMap as Map, BinaryHeap};
+
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt::Display;
+#[cfg(not(feature = "std"))]
+use core::error::Error;
 
-use std::io::{BufRead, Seek, SeekFrom, Write};
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 use bitvec::prelude::{bitvec, LittleEndian};
 
-mod new_bitvec;
-use new_bitvec::NewBitVec;
+mod bit_reader;
+use bit_reader::BitReader;
+
+mod io;
+pub use io::{Read, Write};
 
 //Only use this bitvec type throughout this crate
 type BitVec = bitvec::prelude::BitVec<LittleEndian, u8>;
@@ -38,12 +59,12 @@ macro_rules! encoding {
 // }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-enum HuffmanNode {
-    Leaf(Count, char),
-    Interior(Count, HashMap<char, BitVec>),
+enum HuffmanNode<T: Eq + Hash + Clone + Ord> {
+    Leaf(Count, T),
+    Interior(Count, Map<T, BitVec>),
 }
 
-impl Ord for HuffmanNode {
+impl<T: Eq + Hash + Clone + Ord> Ord for HuffmanNode<T> {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
             (HuffmanNode::Leaf(c1, _), HuffmanNode::Leaf(c2, _)) => c1.cmp(c2),
@@ -60,7 +81,7 @@ impl Ord for HuffmanNode {
     }
 }
 
-impl PartialOrd for HuffmanNode {
+impl<T: Eq + Hash + Clone + Ord> PartialOrd for HuffmanNode<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
@@ -79,7 +100,7 @@ impl HuffmanEncodingError {
 }
 
 impl Display for HuffmanEncodingError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "HuffmanEndocingError: {}", self.bitpattern)
     }
 }
@@ -89,10 +110,13 @@ impl Error for HuffmanEncodingError {}
 /// combines two Huffman Nodes, updating encodings
 /// for every character, and updating the total character count
 /// the left subtree gets an added 0 to the encoding and the right a 1
-fn combine(left: HuffmanNode, right: HuffmanNode) -> HuffmanNode {
+fn combine<T: Eq + Hash + Clone + Ord>(
+    left: HuffmanNode<T>,
+    right: HuffmanNode<T>,
+) -> HuffmanNode<T> {
     match (left, right) {
         (HuffmanNode::Leaf(lcount, l_ch), HuffmanNode::Leaf(rcount, r_ch)) => {
-            let mut code = HashMap::new();
+            let mut code = Map::new();
             code.insert(l_ch, encoding!(0));
             code.insert(r_ch, encoding!(1));
             HuffmanNode::Interior(lcount + rcount, code)
@@ -133,87 +157,158 @@ fn combine(left: HuffmanNode, right: HuffmanNode) -> HuffmanNode {
     }
 }
 
-fn count_chars(
-    r: &mut impl BufRead,
-) -> Result<HashMap<char, Count>, Box<dyn Error>> {
-    let mut frequencies = HashMap::new();
-    let mut num_lines = 0;
-    for (line_number, line) in r.lines().enumerate() {
-        let line = line?;
-        num_lines = line_number;
-        for ch in line.chars() {
-            let freq = frequencies.entry(ch).or_insert(0);
-            *freq += 1;
-        }
+/// Counts raw `u8` values with no line splitting, so binary input
+/// round-trips exactly.
+fn count_bytes(bytes: &[u8]) -> Map<u8, Count> {
+    let mut frequencies = Map::new();
+    for &byte in bytes {
+        let freq = frequencies.entry(byte).or_insert(0);
+        *freq += 1;
     }
-    frequencies.insert('\n', num_lines as Count); //TODO: cast properly
-    Ok(frequencies)
+    frequencies
 }
 
-fn char_count_to_huffman_encoding(
-    char_count: HashMap<char, Count>,
-) -> HashMap<char, BitVec> {
-    let mut huffman_heap = char_count.into_iter().fold(
-        BinaryHeap::new(),
-        |mut heap, (character, frequency)| {
-            heap.push(Reverse(HuffmanNode::Leaf(frequency, character)));
-            heap
-        },
-    );
+/// Builds the canonical Huffman table for `counts`. A `counts` with no
+/// entries (empty input) yields an empty table. A `counts` with exactly one
+/// distinct symbol is degenerate: the Huffman tree has no branching, so the
+/// symbol is given a well-defined single-bit code (`0`) rather than a
+/// zero-length one, which the trie-walking decoder couldn't otherwise step
+/// through.
+fn counts_to_huffman_encoding<T: Eq + Hash + Clone + Ord>(
+    counts: Map<T, Count>,
+) -> Result<Map<T, BitVec>, HuffmanEncodingError> {
+    if counts.is_empty() {
+        return Ok(Map::new());
+    }
+    let mut huffman_heap =
+        counts
+            .into_iter()
+            .fold(BinaryHeap::new(), |mut heap, (symbol, frequency)| {
+                heap.push(Reverse(HuffmanNode::Leaf(frequency, symbol)));
+                heap
+            });
     while huffman_heap.len() > 1 {
         let node1 = huffman_heap.pop().unwrap().0;
         let node2 = huffman_heap.pop().unwrap().0;
         let combined = combine(node1, node2);
         huffman_heap.push(Reverse(combined));
     }
-    match huffman_heap.pop().unwrap().0 {
-        HuffmanNode::Interior(_total_chars, encoding) => encoding,
-        HuffmanNode::Leaf(_total_chars, character) => {
-            let mut encoding = HashMap::new();
-            encoding.insert(character, encoding!());
+    let node = huffman_heap
+        .pop()
+        .ok_or_else(|| HuffmanEncodingError::new(encoding!()))?
+        .0;
+    let encoding = match node {
+        HuffmanNode::Interior(_total_count, encoding) => encoding,
+        HuffmanNode::Leaf(_total_count, symbol) => {
+            let mut encoding = Map::new();
+            encoding.insert(symbol, encoding!(0));
             encoding
         }
+    };
+    let lengths: Vec<(T, u8)> = encoding
+        .into_iter()
+        .map(|(symbol, code)| (symbol, code.len() as u8))
+        .collect();
+    Ok(assign_canonical_codes(lengths))
+}
+
+/// Assigns canonical Huffman codes given only each symbol's code length.
+/// Symbols are ordered by `(length, symbol)`, the first code is all zero
+/// bits, and each subsequent code is `(prev_code + 1) << (len - prev_len)`.
+/// Codes are built directly as `BitVec`s (increment, then pad with zero bits
+/// out to `len`) rather than through a fixed-width integer, so there's no
+/// ceiling on how long a code can get. Rebuilding codes this way means only
+/// the lengths need to be stored in the header; `build_byte_decoding_table`
+/// runs the same recurrence to recover the codes.
+fn assign_canonical_codes<T: Eq + Hash + Clone + Ord>(
+    mut lengths: Vec<(T, u8)>,
+) -> Map<T, BitVec> {
+    lengths.sort_by_key(|(symbol, len)| (*len, symbol.clone()));
+    let mut codes = Map::new();
+    let mut prev_code: BitVec = encoding!();
+    for (i, (symbol, len)) in lengths.into_iter().enumerate() {
+        let mut code = if i == 0 {
+            encoding!()
+        } else {
+            increment_bitvec(prev_code)
+        };
+        while (code.len() as u8) < len {
+            code.push(false);
+        }
+        codes.insert(symbol, code.clone());
+        prev_code = code;
     }
+    codes
 }
 
-fn serialize_huffman_encoding(encoding: &HashMap<char, BitVec>) -> Vec<u8> {
-    let mut buffer: Vec<u8> = Vec::with_capacity(encoding.len());
-    let mut utf8_buffer = [0_u8; 4];
-    let mut encoding: Vec<_> = encoding.clone().into_iter().collect();
-    encoding.sort_by_key(|(ch, _bitvec)| *ch);
-    for (character, code) in encoding {
-        let utf8_slice = character.encode_utf8(&mut utf8_buffer).as_bytes();
-        buffer.extend(utf8_slice.iter());
-        for bit in code {
-            buffer.push(if bit { b'1' } else { b'0' });
+/// Adds one to `code`, treated as a most-significant-bit-first binary
+/// number, growing it by a bit on carry-out (e.g. `111` becomes `1000`)
+/// rather than wrapping.
+fn increment_bitvec(mut code: BitVec) -> BitVec {
+    let mut carried = false;
+    for mut bit in code.iter_mut().rev() {
+        if !*bit {
+            *bit = true;
+            carried = true;
+            break;
         }
-        buffer.push(b'\n');
+        *bit = false;
+    }
+    if !carried {
+        code.insert(0, true);
+    }
+    code
+}
+
+/// Serializes a canonical Huffman table as a symbol count followed by, for
+/// each symbol, the raw byte and its code length.
+fn serialize_byte_huffman_encoding(encoding: &Map<u8, BitVec>) -> Vec<u8> {
+    let mut lengths: Vec<(u8, u8)> = encoding
+        .iter()
+        .map(|(&byte, code)| (byte, code.len() as u8))
+        .collect();
+    lengths.sort_by_key(|(byte, len)| (*len, *byte));
+
+    let mut buffer: Vec<u8> = Vec::with_capacity(4 + lengths.len() * 2);
+    buffer.extend_from_slice(&(lengths.len() as u32).to_le_bytes());
+    for (byte, length) in lengths {
+        buffer.push(byte);
+        buffer.push(length);
     }
     buffer
 }
 
-pub fn encode<R, W>(mut r: R, mut out: W) -> Result<(), Box<dyn Error>>
-where
-    R: BufRead + Seek,
-    W: Write,
-{
-    let char_count = count_chars(&mut r)?;
-    let encoding = char_count_to_huffman_encoding(char_count);
-    let serialized_encoding: Vec<u8> = serialize_huffman_encoding(&encoding);
+/// Thin `char`-oriented wrapper around [`encode_bytes`]: Huffman coding
+/// always runs over bytes, so this just checks up front that the input is
+/// well-formed UTF-8 before compressing it as raw bytes.
+pub fn encode<R: Read, W: Write>(mut r: R, out: W) -> Result<(), Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    core::str::from_utf8(&bytes)?;
+    encode_bytes(&bytes[..], out)
+}
+
+/// Byte-oriented counterpart to [`encode`]: reads `r` as raw `u8`s with no
+/// UTF-8 check and no line splitting, so arbitrary binary input (images,
+/// executables, ...) round-trips exactly. Available without `std`, via the [`Read`]/[`Write`]
+/// abstraction.
+pub fn encode_bytes<R: Read, W: Write>(mut r: R, mut out: W) -> Result<(), Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+
+    let byte_count = count_bytes(&bytes);
+    let total_symbols: u64 = byte_count.values().map(|&count| count as u64).sum();
+    let encoding = counts_to_huffman_encoding(byte_count)?;
+    let serialized_encoding: Vec<u8> = serialize_byte_huffman_encoding(&encoding);
     out.write_all(&serialized_encoding)?;
-    out.write_all(b"\n\n")?; //separation between encoding and body of text
-    r.seek(SeekFrom::Start(0))?;
-    let endline_encoding = encoding.get(&'\n').unwrap();
+    out.write_all(&total_symbols.to_le_bytes())?;
 
     let mut buffer = encoding!();
-    for line in r.lines() {
-        let line = line?;
-        for character in line.chars() {
-            let code = encoding.get(&character).unwrap();
-            let mut code = code.clone();
-            buffer.append(&mut code);
-        }
-        buffer.append(&mut endline_encoding.clone());
+    for &byte in &bytes {
+        let code = encoding
+            .get(&byte)
+            .ok_or_else(|| HuffmanEncodingError::new(encoding!()))?;
+        buffer.append(&mut code.clone());
         if buffer.len() > 8 {
             //split off incomplete byte from buffer
             let split_index = buffer.len() - buffer.len() % 8;
@@ -223,79 +318,253 @@ where
             buffer = buffer_remainder;
         }
     }
-    let bytes: Vec<u8> = buffer.into();
-    out.write_all(&bytes[..])?;
+    let tail: Vec<u8> = buffer.into();
+    out.write_all(&tail[..])?;
     Ok(())
 }
 
-fn build_decoding_table<R: BufRead>(
-    r: &mut R,
-) -> Result<HashMap<BitVec, char>, Box<dyn Error>> {
-    let mut huffman_encoding: HashMap<BitVec, char> = HashMap::new();
-    // parse huffman encoding for each character
-    let mut line = String::new();
-    loop {
-        r.read_line(&mut line)?;
-        line.pop(); // remove trailing '\n'
-        let mut chars = line.chars();
-        let encoded_char = match chars.next() {
-            Some(character) => character,
-            None => {
-                // this was an empty line
-                line.clear();
-                r.read_line(&mut line)?;
-                if line == "\n" {
-                    break; // two empty lines -> end of encoding section
-                }
-                line.pop();
-                chars = line.chars();
-                '\n'
-            }
-        };
-        let mut encoding = encoding!();
-        for bit in chars {
-            match bit {
-                '0' => encoding.push(false),
-                '1' => encoding.push(true),
-                _ => return Err(Box::new(HuffmanEncodingError::new(encoding))),
-            }
-        }
-        line.clear();
-        huffman_encoding.insert(encoding, encoded_char);
+/// Splits `n` bytes off the front of `cursor`, advancing it, or errors if
+/// fewer than `n` bytes remain.
+fn take<'a>(cursor: &mut &'a [u8], n: usize) -> Result<&'a [u8], HuffmanEncodingError> {
+    if cursor.len() < n {
+        return Err(HuffmanEncodingError::new(encoding!()));
     }
-    Ok(huffman_encoding)
+    let (head, tail) = cursor.split_at(n);
+    *cursor = tail;
+    Ok(head)
 }
 
-pub fn decode<R, W>(mut r: R, mut out: W) -> Result<(), Box<dyn Error>>
-where
-    R: BufRead,
-    W: Write,
-{
-    let encoding: HashMap<BitVec, char> = build_decoding_table(&mut r)?;
-    let encoding: HashMap<NewBitVec, char> = encoding
+/// Parses the table `serialize_byte_huffman_encoding` wrote: a symbol count
+/// followed by, for each symbol, the raw byte and its code length. `cursor`
+/// is advanced past the table, leaving the rest of the payload for the
+/// caller to read next.
+fn build_byte_decoding_table(
+    cursor: &mut &[u8],
+) -> Result<Map<BitVec, u8>, HuffmanEncodingError> {
+    let symbol_count = u32::from_le_bytes(take(cursor, 4)?.try_into().unwrap()) as usize;
+    // Each entry is 2 bytes, so a symbol_count that would need more entries
+    // than remain in `cursor` is corrupt; reject it before the allocation
+    // below, rather than letting an attacker-controlled u32 (up to ~4.3e9)
+    // drive a multi-gigabyte `Vec::with_capacity`.
+    if symbol_count > cursor.len() / 2 {
+        return Err(HuffmanEncodingError::new(encoding!()));
+    }
+
+    let mut lengths = Vec::with_capacity(symbol_count);
+    for _ in 0..symbol_count {
+        let entry = take(cursor, 2)?;
+        lengths.push((entry[0], entry[1]));
+    }
+
+    let huffman_encoding = assign_canonical_codes(lengths);
+    Ok(huffman_encoding
         .into_iter()
-        .map(|(bitvec, ch)| (NewBitVec::from(bitvec), ch))
-        .collect();
+        .map(|(byte, code)| (code, byte))
+        .collect())
+}
 
-    let bytes = r.bytes();
-    let mut bit_buffer: BitVec = BitVec::new();
-    let mut to_encode: NewBitVec = NewBitVec::new();
-    for byte in bytes {
-        let byte = byte?;
-        let mut tmp: BitVec = BitVec::from_element(byte);
-        tmp.reverse();
-        bit_buffer.append(&mut tmp);
-        while let Some(bit) = bit_buffer.pop() {
-            to_encode.push(bit);
-            if to_encode.len() > Count::min_value().count_zeros() as usize {
-                return Err(Box::new(HuffmanEncodingError::new(to_encode.into())));
-            }
-            if let Some(ch) = encoding.get(&to_encode) {
-                let mut utf8_buf = [0_u8; 4];
-                out.write_all(ch.encode_utf8(&mut utf8_buf).as_bytes())?;
-                to_encode.clear();
-            }
+/// A node in the decoding tree: interior nodes hold a `left`/`right` child
+/// for the next `0`/`1` bit, leaves hold the symbol the path so far spells
+/// out.
+#[derive(Debug)]
+struct DecodingNode<T> {
+    left: Option<Box<DecodingNode<T>>>,
+    right: Option<Box<DecodingNode<T>>>,
+    leaf: Option<T>,
+}
+
+impl<T> Default for DecodingNode<T> {
+    fn default() -> DecodingNode<T> {
+        DecodingNode {
+            left: None,
+            right: None,
+            leaf: None,
         }
     }
+}
+
+impl<T> DecodingNode<T> {
+    fn insert(&mut self, code: BitVec, symbol: T) {
+        let mut node = self;
+        for bit in code {
+            let branch = if bit { &mut node.right } else { &mut node.left };
+            node = branch.get_or_insert_with(|| Box::new(DecodingNode::default()));
+        }
+        node.leaf = Some(symbol);
+    }
+}
+
+fn build_decoding_tree<T>(encoding: Map<BitVec, T>) -> DecodingNode<T> {
+    let mut root = DecodingNode::default();
+    for (code, symbol) in encoding {
+        root.insert(code, symbol);
+    }
+    root
+}
+
+/// Checks that the bits left over after the last complete symbol are the
+/// zero padding `encode` uses to fill out the trailing byte. Any `1` among
+/// them means the stream was truncated or corrupted mid-symbol.
+fn verify_ending(trailing_bits: &BitVec) -> Result<(), HuffmanEncodingError> {
+    if trailing_bits.clone().into_iter().any(|bit| bit) {
+        Err(HuffmanEncodingError::new(trailing_bits.clone()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Thin `char`-oriented wrapper around [`decode_bytes`]: decodes into memory
+/// and checks the result is well-formed UTF-8 before writing it to `out`.
+pub fn decode<R: Read, W: Write>(r: R, mut out: W) -> Result<(), Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    decode_bytes(r, &mut bytes)?;
+    core::str::from_utf8(&bytes)?;
+    out.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Byte-oriented counterpart to [`decode`]: walks the decoding trie and
+/// emits the raw `u8`s with no UTF-8 check. Available without
+/// `std`, via the [`Read`]/[`Write`] abstraction.
+pub fn decode_bytes<R: Read, W: Write>(mut r: R, mut out: W) -> Result<(), Box<dyn Error>> {
+    let mut payload = Vec::new();
+    r.read_to_end(&mut payload)?;
+    let mut cursor = &payload[..];
+
+    let encoding: Map<BitVec, u8> = build_byte_decoding_table(&mut cursor)?;
+    let tree = build_decoding_tree(encoding);
+
+    let total_symbols = u64::from_le_bytes(take(&mut cursor, 8)?.try_into().unwrap());
+
+    let mut reader = BitReader::new(cursor);
+
+    let mut node = &tree;
+    let mut trailing_bits: BitVec = encoding!();
+    let mut decoded: u64 = 0;
+    while decoded < total_symbols {
+        let bit = reader
+            .read_bit()
+            .ok_or_else(|| HuffmanEncodingError::new(trailing_bits.clone()))?;
+        let next = if bit { &node.right } else { &node.left };
+        node = next
+            .as_deref()
+            .ok_or_else(|| HuffmanEncodingError::new(trailing_bits.clone()))?;
+        trailing_bits.push(bit);
+        if let Some(byte) = node.leaf {
+            out.write_all(&[byte])?;
+            node = &tree;
+            trailing_bits.clear();
+            decoded += 1;
+        }
+    }
+    while let Some(bit) = reader.read_bit() {
+        trailing_bits.push(bit);
+    }
+    verify_ending(&trailing_bits)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip_bytes(input: &[u8]) {
+        let mut compressed = Vec::new();
+        encode_bytes(input, &mut compressed).unwrap();
+        let mut decompressed = Vec::new();
+        decode_bytes(&compressed[..], &mut decompressed).unwrap();
+        assert_eq!(decompressed, input);
+    }
+
+    #[test]
+    fn round_trips_empty_input() {
+        round_trip_bytes(b"");
+    }
+
+    #[test]
+    fn round_trips_single_distinct_symbol() {
+        round_trip_bytes(b"aaaaaaaaaa");
+    }
+
+    #[test]
+    fn round_trips_text_without_trailing_newline() {
+        round_trip_bytes(b"hello world, hello world, hello!");
+    }
+
+    #[test]
+    fn round_trips_arbitrary_binary_data() {
+        round_trip_bytes(&[0xff, 0xfe, 0x00, 0x01, 0x02, 0x00, 0xff, 0x10]);
+    }
+
+    #[test]
+    fn char_api_round_trips_multibyte_utf8() {
+        let input = "héllo → wörld\n";
+        let mut compressed = Vec::new();
+        encode(input.as_bytes(), &mut compressed).unwrap();
+        let mut decompressed = Vec::new();
+        decode(&compressed[..], &mut decompressed).unwrap();
+        assert_eq!(decompressed, input.as_bytes());
+    }
+
+    #[test]
+    fn char_api_rejects_invalid_utf8() {
+        let invalid = [0xff_u8, 0xfe, 0xfd];
+        let mut compressed = Vec::new();
+        assert!(encode(&invalid[..], &mut compressed).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_corrupted_trailing_byte() {
+        let mut compressed = Vec::new();
+        encode_bytes(&b"aaaabbbc"[..], &mut compressed).unwrap();
+        // Flip the last byte so the zero padding verify_ending() expects
+        // no longer holds.
+        *compressed.last_mut().unwrap() ^= 0xff;
+        let mut decompressed = Vec::new();
+        assert!(decode_bytes(&compressed[..], &mut decompressed).is_err());
+    }
+
+    #[test]
+    fn round_trips_large_skewed_alphabet() {
+        // Fibonacci-weighted frequencies force the most unbalanced Huffman
+        // tree possible for a given alphabet size, so with 34 distinct
+        // symbols the deepest code reaches a length of 33 bits — longer than
+        // a 32-bit integer could hold, which `assign_canonical_codes` must
+        // handle since it builds codes as `BitVec`s. Using synthetic counts
+        // rather than literally repeating each byte that many times keeps the
+        // test fast while still exercising code lengths past 32 bits.
+        let mut counts: Map<u8, Count> = Map::new();
+        let mut fib = (1_u32, 1_u32);
+        for symbol in 0_u8..34 {
+            counts.insert(symbol, fib.0);
+            fib = (fib.1, fib.0 + fib.1);
+        }
+        let encoding = counts_to_huffman_encoding(counts).unwrap();
+        let max_len = encoding.values().map(|code| code.len()).max().unwrap();
+        assert!(
+            max_len >= 33,
+            "expected a code at least 33 bits long, got {}",
+            max_len
+        );
+
+        let serialized = serialize_byte_huffman_encoding(&encoding);
+        let mut cursor = &serialized[..];
+        let decoding_table = build_byte_decoding_table(&mut cursor).unwrap();
+        let roundtripped: Map<u8, BitVec> = decoding_table
+            .into_iter()
+            .map(|(code, byte)| (byte, code))
+            .collect();
+        assert_eq!(roundtripped, encoding);
+    }
+
+    #[test]
+    fn decode_rejects_symbol_count_larger_than_remaining_input() {
+        // A symbol_count of u32::MAX with almost nothing behind it must be
+        // rejected before build_byte_decoding_table tries to allocate a
+        // `lengths` vector sized off it.
+        let mut malformed = u32::MAX.to_le_bytes().to_vec();
+        malformed.extend_from_slice(&[0_u8; 4]);
+        let mut decompressed = Vec::new();
+        assert!(decode_bytes(&malformed[..], &mut decompressed).is_err());
+    }
+}